@@ -0,0 +1,97 @@
+use std::{env, fs, io};
+
+use crate::core::PublicIp;
+
+const CLOUDFLARE_IP_CACHE: &str = "CLOUDFLARE_IP_CACHE";
+
+/// Persists the last successfully-applied public IP between poll cycles so
+/// `Updater::run` can skip a round-trip to the Cloudflare API when nothing
+/// has changed.
+pub struct IpCache {
+    path: std::path::PathBuf,
+}
+
+impl Default for IpCache {
+    fn default() -> Self {
+        let path = env::var(CLOUDFLARE_IP_CACHE)
+            .map(std::path::PathBuf::from)
+            .unwrap_or_else(|_| env::temp_dir().join("change_flare_ip.cache"));
+
+        Self { path }
+    }
+}
+
+impl IpCache {
+    /// Reads the last cached address. A missing or corrupt cache file is
+    /// treated as "unknown" rather than an error, which forces a full sync.
+    pub fn load(&self) -> PublicIp {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => parse(&contents),
+            Err(_) => PublicIp::default(),
+        }
+    }
+
+    /// Overwrites the cache with `ip`.
+    pub fn store(&self, ip: &PublicIp) -> io::Result<()> {
+        let contents = format!(
+            "v4={}\nv6={}\n",
+            ip.v4.map(|v| v.to_string()).unwrap_or_default(),
+            ip.v6.map(|v| v.to_string()).unwrap_or_default(),
+        );
+        fs::write(&self.path, contents)
+    }
+}
+
+fn parse(contents: &str) -> PublicIp {
+    let mut ip = PublicIp::default();
+    for line in contents.lines() {
+        if let Some(val) = line.strip_prefix("v4=") {
+            ip.v4 = val.parse().ok();
+        } else if let Some(val) = line.strip_prefix("v6=") {
+            ip.v6 = val.parse().ok();
+        }
+    }
+    ip
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    fn temp_cache() -> IpCache {
+        let path = env::temp_dir().join(format!("change_flare_cache_test_{:?}", std::thread::current().id()));
+        IpCache { path }
+    }
+
+    #[test]
+    fn store_then_load_round_trips() {
+        let cache = temp_cache();
+        let ip = PublicIp {
+            v4: Some(Ipv4Addr::new(203, 0, 113, 1)),
+            v6: Some(Ipv6Addr::new(0x2001, 0x0db8, 0, 0, 0, 0, 0, 1)),
+        };
+
+        cache.store(&ip).unwrap();
+
+        assert_eq!(cache.load(), ip);
+        fs::remove_file(&cache.path).ok();
+    }
+
+    #[test]
+    fn load_missing_file_defaults_to_unknown() {
+        let cache = temp_cache();
+        fs::remove_file(&cache.path).ok();
+
+        assert_eq!(cache.load(), PublicIp::default());
+    }
+
+    #[test]
+    fn load_corrupt_file_defaults_to_unknown() {
+        let cache = temp_cache();
+        fs::write(&cache.path, "not a valid cache file").unwrap();
+
+        assert_eq!(cache.load(), PublicIp::default());
+        fs::remove_file(&cache.path).ok();
+    }
+}
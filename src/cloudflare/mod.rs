@@ -1,13 +1,23 @@
 use reqwest::header::{HeaderMap, HeaderValue};
-use std::{env, net::IpAddr};
+use std::{
+    env,
+    error::Error,
+    fs,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
 use ureq::serde_json;
 
 use serde::Deserialize;
 
+use log::{error, info, warn};
+
 use crate::core::{ApiTrait, Record};
 const CLOUDFLARE_POLL_RATE: &str = "CLOUDFLARE_POLL_RATE";
 const CLOUDFLARE_API_KEY: &str = "CLOUDFLARE_API_KEY";
 const CLOUDFLARE_ZONE_ID: &str = "CLOUDFLARE_ZONE_ID";
+const CLOUDFLARE_CONFIG_FILE: &str = "CLOUDFLARE_CONFIG_FILE";
+const DEFAULT_CONFIG_FILE: &str = "config.json";
+const DEFAULT_BASE_URL: &str = "https://api.cloudflare.com/client/v4";
 
 #[derive(Default)]
 pub struct CloudFlareApi {
@@ -28,69 +38,70 @@ impl ApiTrait for CloudFlareApi {
 
     fn get_records(&mut self) -> &Vec<CloudFlareRecord> {
         let client = reqwest::blocking::Client::new();
-        let mut headers = HeaderMap::new();
-
-        let auth_header = match HeaderValue::from_str(&format!("Bearer {}", self.config.api_key)) {
-            Ok(header) => header,
-            Err(e) => {
-                eprintln!("Invalid API key format: {}", e);
-                return &self.records;
-            }
-        };
-        headers.insert("Authorization", auth_header);
 
-        let response = match client
-            .get(&format!(
-                "https://api.cloudflare.com/client/v4/zones/{}/dns_records",
-                self.config.zone_id
-            ))
-            .headers(headers)
-            .send()
-        {
-            Ok(resp) => resp,
-            Err(e) => {
-                eprintln!("Failed to send request: {}", e);
-                return &self.records;
-            }
-        };
+        let mut records = Vec::new();
+        for zone in &self.config.zones {
+            let mut headers = HeaderMap::new();
 
-        let response_text = match response.text() {
-            Ok(text) => text,
-            Err(e) => {
-                eprintln!("Failed to get response text: {}", e);
-                return &self.records;
-            }
-        };
-
-        let response: CloudflareResponse = match serde_json::from_str(&response_text) {
-            Ok(resp) => resp,
-            Err(e) => {
-                eprintln!(
-                    "Failed to parse response: {}\nResponse text: {}",
-                    e, response_text
-                );
-                return &self.records;
+            let auth_header =
+                match HeaderValue::from_str(&format!("Bearer {}", self.config.api_key)) {
+                    Ok(header) => header,
+                    Err(e) => {
+                        error!("Invalid API key format: {}", e);
+                        continue;
+                    }
+                };
+            headers.insert("Authorization", auth_header);
+
+            let response = match client
+                .get(&format!(
+                    "{}/zones/{}/dns_records",
+                    self.config.base_url, zone.zone_id
+                ))
+                .headers(headers)
+                .send()
+            {
+                Ok(resp) => resp,
+                Err(e) => {
+                    error!("Failed to send request: {}", e);
+                    continue;
+                }
+            };
+
+            let response_text = match response.text() {
+                Ok(text) => text,
+                Err(e) => {
+                    error!("Failed to get response text: {}", e);
+                    continue;
+                }
+            };
+
+            let response: CloudflareResponse = match serde_json::from_str(&response_text) {
+                Ok(resp) => resp,
+                Err(e) => {
+                    error!(
+                        "Failed to parse response: {}\nResponse text: {}",
+                        e, response_text
+                    );
+                    continue;
+                }
+            };
+
+            if !response.success {
+                error!("Cloudflare API request failed");
+                continue;
             }
-        };
 
-        if !response.success {
-            eprintln!("Cloudflare API request failed");
-            return &self.records;
-        }
-
-        self.records = response
-            .result
-            .into_iter()
-            .filter_map(|r| {
+            records.extend(response.result.into_iter().filter_map(|r| {
                 let content = match r.content.parse() {
                     Ok(ip) => ip,
                     Err(e) => {
-                        eprintln!("Invalid IP address for record {}: {}", r.name, e);
+                        warn!("Invalid IP address for record {}: {}", r.name, e);
                         return None;
                     }
                 };
 
-                Some(CloudFlareRecord {
+                let record = CloudFlareRecord {
                     content,
                     name: r.name,
                     record_type: r.r#type,
@@ -98,32 +109,42 @@ impl ApiTrait for CloudFlareApi {
                     proxied: r.proxied,
                     zone_id: r.zone_id,
                     record_id: Some(r.id),
-                })
-            })
-            .collect();
+                };
 
+                // An empty entry list means "manage everything in this zone",
+                // matching the pre-config-file behaviour.
+                if zone.manages(&record) {
+                    Some(record)
+                } else {
+                    None
+                }
+            }));
+        }
+
+        self.records = records;
         &self.records
     }
-    fn update_record(&mut self, record: &CloudFlareRecord) -> CloudFlareRecord {
+    fn update_record(&mut self, record: &CloudFlareRecord) -> Result<CloudFlareRecord, Box<dyn Error>> {
         let client = reqwest::blocking::Client::new();
         let mut headers = HeaderMap::new();
 
         headers.insert(
             "Authorization",
             HeaderValue::from_str(&format!("Bearer {}", self.config.api_key))
-                .map_err(|e| eprintln!("Invalid API key format: {}", e))
+                .map_err(|e| error!("Invalid API key format: {}", e))
                 .unwrap_or_else(|e| {
-                    eprintln!("Invalid API key format");
+                    error!("Invalid API key format");
                     HeaderValue::from_static("")
                 }),
         );
         headers.insert("Content-Type", HeaderValue::from_static("application/json"));
 
         let url = format!(
-            "https://api.cloudflare.com/client/v4/zones/{}/dns_records/{}",
-            self.config.zone_id,
+            "{}/zones/{}/dns_records/{}",
+            self.config.base_url,
+            record.zone_id,
             record.get_id().unwrap_or_else(|| {
-                eprintln!("Record ID not found");
+                error!("Record ID not found");
                 String::new()
             })
         );
@@ -136,19 +157,111 @@ impl ApiTrait for CloudFlareApi {
             "ttl": record.ttl,
         });
 
-        match client
+        client
             .put(&url)
             .headers(headers)
             .json(&payload)
             .send()
             .and_then(|r| r.error_for_status())
-        {
-            Ok(_) => record.clone(),
-            Err(e) => {
-                eprintln!("Failed to update record: {}", e);
-                record.clone()
+            .map_err(|e| {
+                error!("Failed to update record: {}", e);
+                Box::new(e) as Box<dyn Error>
+            })?;
+
+        Ok(record.clone())
+    }
+
+    fn create_record(&mut self, record: &CloudFlareRecord) -> Result<CloudFlareRecord, Box<dyn Error>> {
+        let client = reqwest::blocking::Client::new();
+        let mut headers = HeaderMap::new();
+
+        headers.insert(
+            "Authorization",
+            HeaderValue::from_str(&format!("Bearer {}", self.config.api_key))
+                .map_err(|e| error!("Invalid API key format: {}", e))
+                .unwrap_or_else(|e| {
+                    error!("Invalid API key format");
+                    HeaderValue::from_static("")
+                }),
+        );
+        headers.insert("Content-Type", HeaderValue::from_static("application/json"));
+
+        let url = format!("{}/zones/{}/dns_records", self.config.base_url, record.zone_id);
+
+        let payload = serde_json::json!({
+            "content": record.content.to_string(),
+            "name": record.name,
+            "proxied": record.proxied,
+            "type": record.record_type,
+            "ttl": record.ttl,
+        });
+
+        let response = client.post(&url).headers(headers).json(&payload).send().map_err(|e| {
+            error!("Failed to create record: {}", e);
+            Box::new(e) as Box<dyn Error>
+        })?;
+
+        let response_text = response.text().map_err(|e| {
+            error!("Failed to get response text: {}", e);
+            Box::new(e) as Box<dyn Error>
+        })?;
+
+        let response: CloudflareRecordResponse =
+            serde_json::from_str(&response_text).map_err(|e| {
+                error!(
+                    "Failed to parse response: {}\nResponse text: {}",
+                    e, response_text
+                );
+                Box::new(e) as Box<dyn Error>
+            })?;
+
+        if !response.success {
+            error!("Cloudflare API request failed");
+            return Err("Cloudflare API request failed".into());
+        }
+
+        Ok(CloudFlareRecord {
+            record_id: Some(response.result.id),
+            ..record.clone()
+        })
+    }
+
+    /// Configured entries that have no matching live record yet. An entry's
+    /// `proxied`/`ttl` settings are only consulted here, at creation time —
+    /// once a record is live, reconciliation (see `Updater::run`) only ever
+    /// touches its content to track the host's IP, so editing `proxied`/`ttl`
+    /// for an already-existing entry in the config file has no effect on it.
+    fn get_missing_records(&self) -> Vec<CloudFlareRecord> {
+        let mut missing = Vec::new();
+
+        for zone in &self.config.zones {
+            for entry in &zone.entries {
+                let already_live = self
+                    .records
+                    .iter()
+                    .any(|r| r.name == entry.name && r.record_type == entry.record_type);
+                if already_live {
+                    continue;
+                }
+
+                let content = match entry.record_type.as_str() {
+                    "AAAA" => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+                    _ => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+                };
+
+                missing.push(CloudFlareRecord {
+                    content,
+                    name: entry.name.clone(),
+                    record_type: entry.record_type.clone(),
+                    ttl: entry.ttl,
+                    proxied: entry.proxied,
+                    zone_id: zone.zone_id.clone(),
+                    record_id: None,
+                });
             }
         }
+
+        missing
     }
 
     fn get_poll_rate(&self) -> usize {
@@ -159,16 +272,62 @@ impl ApiTrait for CloudFlareApi {
 struct CloudFlareConfig {
     poll_rate: usize,
     api_key: String,
+    zones: Vec<ZoneConfig>,
+    // Overridable so tests can point requests at a mockito server instead of
+    // the real Cloudflare API.
+    base_url: String,
+}
+
+/// One zone's worth of managed records, as declared in the config file (or
+/// synthesized from `CLOUDFLARE_ZONE_ID` when no config file exists).
+#[derive(Clone, Deserialize)]
+struct ZoneConfig {
     zone_id: String,
+    #[serde(default)]
+    entries: Vec<EntryConfig>,
+}
+
+impl ZoneConfig {
+    /// Whether `record` is one this zone is configured to manage. An empty
+    /// entry list means "manage everything in the zone", preserving the
+    /// behaviour from before the config file existed.
+    fn manages(&self, record: &CloudFlareRecord) -> bool {
+        self.entries.is_empty()
+            || self
+                .entries
+                .iter()
+                .any(|entry| entry.name == record.name && entry.record_type == record.record_type)
+    }
+}
+
+#[derive(Clone, Deserialize)]
+struct EntryConfig {
+    name: String,
+    #[serde(rename = "type")]
+    record_type: String,
+    #[serde(default)]
+    proxied: bool,
+    #[serde(default = "default_ttl")]
+    ttl: u32,
+}
+
+/// Cloudflare's sentinel for "automatic" TTL.
+fn default_ttl() -> u32 {
+    1
+}
+
+#[derive(Deserialize)]
+struct FileConfig {
+    zones: Vec<ZoneConfig>,
 }
 
 impl Default for CloudFlareConfig {
     fn default() -> Self {
         if let Err(e) = dotenvy::dotenv() {
             if e.not_found() {
-                eprintln!(".env file was not found, please create and configure .env");
+                warn!(".env file was not found, please create and configure .env");
             } else {
-                eprintln!(".env file was found but error has occurred: {}", e);
+                warn!(".env file was found but error has occurred: {}", e);
             }
         }
 
@@ -178,25 +337,18 @@ impl Default for CloudFlareConfig {
             .unwrap_or(300);
 
         let api_key = env::var(CLOUDFLARE_API_KEY).unwrap_or_else(|_| {
-            eprintln!(
+            error!(
                 "Cloudflare API key was not found. Please configure {}",
                 CLOUDFLARE_API_KEY
             );
             String::new()
         });
 
-        let zone_id = env::var(CLOUDFLARE_ZONE_ID).unwrap_or_else(|_| {
-            eprintln!(
-                "Cloudflare zone ID was not found. Please configure {}",
-                CLOUDFLARE_ZONE_ID
-            );
-            String::new()
-        });
-
         Self {
             poll_rate,
             api_key,
-            zone_id,
+            zones: Self::load_zones(),
+            base_url: DEFAULT_BASE_URL.to_string(),
         }
     }
 }
@@ -204,23 +356,57 @@ impl Default for CloudFlareConfig {
 impl CloudFlareConfig {
     fn new(mut poll_rate: usize, mut api_key: String) -> Self {
         poll_rate = poll_rate.max(60);
-        println!("Cloudflare polling rate set to {} seconds", poll_rate);
+        info!("Cloudflare polling rate set to {} seconds", poll_rate);
 
         api_key = if api_key.is_empty() {
-            println!("CloudFlare API key was empty, trying to configure using .env file");
+            info!("CloudFlare API key was empty, trying to configure using .env file");
             Self::default().api_key
         } else {
             api_key
         };
 
-        let zone_id = Self::default().zone_id;
+        let zones = Self::default().zones;
 
         return Self {
             poll_rate: poll_rate,
             api_key: api_key,
-            zone_id: zone_id,
+            zones: zones,
+            base_url: DEFAULT_BASE_URL.to_string(),
         };
     }
+
+    /// Loads zones from the config file (`CLOUDFLARE_CONFIG_FILE`, default
+    /// `config.json`) if one exists, otherwise falls back to a single zone
+    /// built from `CLOUDFLARE_ZONE_ID` that manages every record it sees.
+    fn load_zones() -> Vec<ZoneConfig> {
+        let config_path =
+            env::var(CLOUDFLARE_CONFIG_FILE).unwrap_or_else(|_| DEFAULT_CONFIG_FILE.to_string());
+
+        if let Ok(contents) = fs::read_to_string(&config_path) {
+            match serde_json::from_str::<FileConfig>(&contents) {
+                Ok(file_config) => return file_config.zones,
+                Err(e) => {
+                    warn!(
+                        "Failed to parse {}: {}, falling back to {}",
+                        config_path, e, CLOUDFLARE_ZONE_ID
+                    );
+                }
+            }
+        }
+
+        let zone_id = env::var(CLOUDFLARE_ZONE_ID).unwrap_or_else(|_| {
+            error!(
+                "Cloudflare zone ID was not found. Please configure {}",
+                CLOUDFLARE_ZONE_ID
+            );
+            String::new()
+        });
+
+        vec![ZoneConfig {
+            zone_id,
+            entries: Vec::new(),
+        }]
+    }
 }
 
 #[derive(Clone)]
@@ -230,7 +416,7 @@ pub struct CloudFlareRecord {
     record_type: String, // A, AAAA, CNAME, etc.
     ttl: u32,
     proxied: bool,
-    zone_id: String,           // Remove this?
+    zone_id: String, // Zone this record belongs to, needed to address it per-zone
     record_id: Option<String>, // Only used if updating an existing record
 }
 
@@ -247,6 +433,10 @@ impl Record<CloudFlareApi> for CloudFlareRecord {
         self.content.clone()
     }
 
+    fn get_record_type(&self) -> &str {
+        &self.record_type
+    }
+
     fn update_content(&self, new_content: IpAddr) -> Self {
         Self {
             content: new_content,
@@ -261,6 +451,12 @@ struct CloudflareResponse {
     result: Vec<CloudflareResult>,
 }
 
+#[derive(Deserialize)]
+struct CloudflareRecordResponse {
+    success: bool,
+    result: CloudflareResult,
+}
+
 #[derive(Deserialize)]
 struct CloudflareResult {
     id: String,
@@ -277,6 +473,7 @@ mod tests {
     use mockito::Mock;
 
     use super::*;
+    use serial_test::serial;
     use std::net::{IpAddr, Ipv4Addr};
 
     fn setup_mock_api() -> (CloudFlareApi, String) {
@@ -284,7 +481,10 @@ mod tests {
         let zone_id = "test_zone_id".to_string();
 
         let mut api = CloudFlareApi::new(60, api_key.clone());
-        api.config.zone_id = zone_id.clone();
+        api.config.zones = vec![ZoneConfig {
+            zone_id: zone_id.clone(),
+            entries: Vec::new(),
+        }];
 
         (api, zone_id)
     }
@@ -302,6 +502,48 @@ mod tests {
     }
 
     #[test]
+    fn zone_config_manages_everything_with_no_entries() {
+        let zone = ZoneConfig {
+            zone_id: "z".to_string(),
+            entries: Vec::new(),
+        };
+
+        assert!(zone.manages(&create_mock_record()));
+    }
+
+    #[test]
+    fn zone_config_manages_only_configured_entries() {
+        let zone = ZoneConfig {
+            zone_id: "z".to_string(),
+            entries: vec![EntryConfig {
+                name: "other.example.com".to_string(),
+                record_type: "A".to_string(),
+                proxied: false,
+                ttl: 1,
+            }],
+        };
+
+        assert!(!zone.manages(&create_mock_record()));
+    }
+
+    #[test]
+    #[serial(change_flare_env)]
+    fn load_zones_falls_back_to_zone_id_env_when_config_file_missing() {
+        env::set_var(CLOUDFLARE_CONFIG_FILE, "/nonexistent/change_flare_test_config.json");
+        env::set_var(CLOUDFLARE_ZONE_ID, "fallback_zone");
+
+        let zones = CloudFlareConfig::load_zones();
+
+        env::remove_var(CLOUDFLARE_CONFIG_FILE);
+        env::remove_var(CLOUDFLARE_ZONE_ID);
+
+        assert_eq!(zones.len(), 1);
+        assert_eq!(zones[0].zone_id, "fallback_zone");
+        assert!(zones[0].entries.is_empty());
+    }
+
+    #[test]
+    #[serial(change_flare_env)]
     fn test_cloudflare_config_new() {
         let api_key = "test_key".to_string();
         let config = CloudFlareConfig::new(30, api_key.clone());
@@ -342,6 +584,7 @@ mod tests {
         });
 
         let mut server = mockito::Server::new();
+        api.config.base_url = format!("{}/client/v4", server.url());
         let _m = server
             .mock("PUT", "/client/v4/zones/test_zone_id/dns_records/record123")
             .with_status(200)
@@ -349,9 +592,43 @@ mod tests {
             .with_body(mock_response.to_string())
             .create();
 
-        let updated_record = api.update_record(&record);
+        let updated_record = api.update_record(&record).unwrap();
 
         assert_eq!(updated_record.name, record.name);
         assert_eq!(updated_record.content, record.content);
     }
+
+    #[test]
+    fn test_create_record() {
+        let (mut api, zone_id) = setup_mock_api();
+        let mut record = create_mock_record();
+        record.record_id = None;
+
+        let mock_response = ureq::json!({
+            "success": true,
+            "result": {
+                "id": "record123",
+                "name": "test.example.com",
+                "content": "127.0.0.1",
+                "type": "A",
+                "ttl": 1,
+                "proxied": false,
+                "zone_id": zone_id
+            }
+        });
+
+        let mut server = mockito::Server::new();
+        api.config.base_url = format!("{}/client/v4", server.url());
+        let _m = server
+            .mock("POST", "/client/v4/zones/test_zone_id/dns_records")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response.to_string())
+            .create();
+
+        let created_record = api.create_record(&record).unwrap();
+
+        assert_eq!(created_record.name, record.name);
+        assert_eq!(created_record.record_id, Some("record123".to_string()));
+    }
 }
@@ -1,23 +1,52 @@
 use std::{
     error::Error,
-    net::{IpAddr, SocketAddr, ToSocketAddrs, UdpSocket},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
     thread,
     time::Duration,
 };
 
-use stunclient::StunClient;
+use log::{debug, error};
+
+use crate::cache::IpCache;
+use crate::ip_source::{self, AddressFamily};
+
+/// The public addresses discovered for this host, per address family.
+///
+/// Either side may be `None` if that family isn't reachable (no STUN
+/// response, no route, etc.) — callers should tolerate a partial result
+/// rather than treating it as a hard failure.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PublicIp {
+    pub v4: Option<Ipv4Addr>,
+    pub v6: Option<Ipv6Addr>,
+}
+
+/// How many poll cycles an IP-unchanged skip is allowed to elide the
+/// `get_records`/`get_missing_records` check for, before one is forced
+/// anyway. Bounds how long a newly added config entry can sit unbootstrapped
+/// while the IP is stable, without paying the API round-trip on every tick.
+const RECONCILE_EVERY_N_CYCLES: usize = 10;
 
 pub struct Updater<T>
 where
     T: ApiTrait,
 {
     api: T,
+    ip_cache: IpCache,
+    // Whether the last full sync hit zero update/create failures. Entries
+    // skipped because no address of their family was available do not count
+    // as failures, so they can still be without a live record.
+    reconciled: bool,
+    cycles_since_reconcile: usize,
 }
 
 impl<T: ApiTrait> Updater<T> {
     pub fn new(poll_rate: usize, api_key: String) -> Self {
         Self {
             api: T::new(poll_rate, api_key),
+            ip_cache: IpCache::default(),
+            reconciled: false,
+            cycles_since_reconcile: 0,
         }
     }
 
@@ -26,23 +55,88 @@ impl<T: ApiTrait> Updater<T> {
             let current_ip = match self.api.check_ip() {
                 Ok(ip) => ip,
                 Err(e) => {
-                    eprintln!("Failed to check IP: {}", e);
+                    error!("Failed to check IP: {}", e);
+                    // Back off instead of spinning when every configured
+                    // source fails (e.g. no network connectivity at all).
+                    thread::sleep(Duration::from_secs(self.api.get_poll_rate() as u64));
                     continue;
                 }
             };
+
+            let ip_unchanged = self.ip_cache.load() == current_ip;
+            let due_for_reconcile_check = self.cycles_since_reconcile >= RECONCILE_EVERY_N_CYCLES;
+
+            // Only safe to skip `get_records`/`get_missing_records` entirely
+            // when the IP hasn't moved, the last full sync was fully
+            // reconciled, and we're not yet due for a periodic re-check (in
+            // case a new entry was added to the config file in the meantime).
+            if ip_unchanged && self.reconciled && !due_for_reconcile_check {
+                debug!("Public IP unchanged ({:?}), skipping sync", current_ip);
+                self.cycles_since_reconcile += 1;
+                thread::sleep(Duration::from_secs(self.api.get_poll_rate() as u64));
+                continue;
+            }
+
+            self.cycles_since_reconcile = 0;
+
             let records = self.api.get_records().clone();
+            let missing = self.api.get_missing_records();
+
+            let mut sync_ok = true;
+
             for record in records.iter() {
+                let new_content = match record.get_record_type() {
+                    "A" => current_ip.v4.map(IpAddr::V4),
+                    "AAAA" => current_ip.v6.map(IpAddr::V6),
+                    _ => None,
+                };
+                let new_content = match new_content {
+                    Some(ip) => ip,
+                    // no address available for this record's family this cycle
+                    None => continue,
+                };
+
                 let mut record_clone = record.clone();
                 // implement a way of checking if something on the host has changed and update the record if it has
                 // for now, just update the record if the IP has changed
-                if record.get_content() != current_ip.ip() {
+                if record.get_content() != new_content {
                     // create a new record with the new IP
-                    record_clone = record_clone.update_content(current_ip.ip());
+                    record_clone = record_clone.update_content(new_content);
                 }
                 if !record_clone.eq(record) {
-                    self.api.update_record(&record_clone);
+                    if let Err(e) = self.api.update_record(&record_clone) {
+                        error!("Failed to update record: {}", e);
+                        sync_ok = false;
+                    }
                 }
             }
+
+            for entry in missing {
+                let new_content = match entry.get_record_type() {
+                    "A" => current_ip.v4.map(IpAddr::V4),
+                    "AAAA" => current_ip.v6.map(IpAddr::V6),
+                    _ => None,
+                };
+                let new_content = match new_content {
+                    Some(ip) => ip,
+                    None => continue,
+                };
+
+                if let Err(e) = self.api.create_record(&entry.update_content(new_content)) {
+                    error!("Failed to create record: {}", e);
+                    sync_ok = false;
+                }
+            }
+
+            self.reconciled = sync_ok;
+
+            // Only a fully successful sync is safe to short-circuit on next time.
+            if sync_ok {
+                if let Err(e) = self.ip_cache.store(&current_ip) {
+                    error!("Failed to write IP cache: {}", e);
+                }
+            }
+
             thread::sleep(Duration::from_secs(self.api.get_poll_rate() as u64));
         }
     }
@@ -52,20 +146,31 @@ pub trait ApiTrait: Sized {
     type RecordType: Record<Self> + Clone;
 
     fn new(poll_rate: usize, api_key: String) -> Self;
-    fn check_ip(&self) -> Result<SocketAddr, Box<dyn Error>> {
-        let local_addr: SocketAddr = "0.0.0.0:0".parse()?;
-        let udp = UdpSocket::bind(local_addr)?;
-        let stun_server = "stun.cloudflare.com:3478"
-            .to_socket_addrs()?
-            .find(|x| x.is_ipv4())
-            .ok_or("No IPv4 address found for STUN server")?;
-
-        let c = StunClient::new(stun_server);
-        let addr = c.query_external_address(&udp)?;
-        Ok(addr)
+    fn check_ip(&self) -> Result<PublicIp, Box<dyn Error>> {
+        let sources = ip_source::configured_sources();
+
+        let v4 = ip_source::lookup_address(&sources, AddressFamily::V4).and_then(|ip| match ip {
+            IpAddr::V4(v4) => Some(v4),
+            IpAddr::V6(_) => None,
+        });
+        let v6 = ip_source::lookup_address(&sources, AddressFamily::V6).and_then(|ip| match ip {
+            IpAddr::V6(v6) => Some(v6),
+            IpAddr::V4(_) => None,
+        });
+
+        if v4.is_none() && v6.is_none() {
+            return Err("Failed to determine public IP over both IPv4 and IPv6".into());
+        }
+
+        Ok(PublicIp { v4, v6 })
     }
-    fn update_record(&mut self, record: &Self::RecordType) -> Self::RecordType;
+    fn update_record(&mut self, record: &Self::RecordType) -> Result<Self::RecordType, Box<dyn Error>>;
+    fn create_record(&mut self, record: &Self::RecordType) -> Result<Self::RecordType, Box<dyn Error>>;
     fn get_records(&mut self) -> &Vec<Self::RecordType>;
+    /// Configured entries that have no matching live record yet, i.e. ones
+    /// `create_record` needs to bootstrap. Must be called after `get_records`
+    /// so it can be compared against what's actually live in the zone.
+    fn get_missing_records(&self) -> Vec<Self::RecordType>;
     fn get_poll_rate(&self) -> usize;
 }
 
@@ -73,6 +178,7 @@ pub trait Record<T: ApiTrait>: 'static {
     fn get_id(&self) -> Option<String>;
     fn get_name(&self) -> String;
     fn get_content(&self) -> IpAddr;
+    fn get_record_type(&self) -> &str;
     fn update_content(&self, new_content: IpAddr) -> Self;
 
     fn eq(&self, other: &Self) -> bool {
@@ -0,0 +1,69 @@
+use std::{error::Error, net::IpAddr};
+
+use serde::Deserialize;
+
+use super::{AddressFamily, IpSource};
+
+const DEFAULT_JSON_URL_V4: &str = "https://api.ipify.org?format=json";
+const DEFAULT_JSON_URL_V6: &str = "https://api6.ipify.org?format=json";
+const DEFAULT_TEXT_URL_V4: &str = "https://ifconfig.me/ip";
+const DEFAULT_TEXT_URL_V6: &str = "https://ifconfig.co/ip";
+
+#[derive(Deserialize)]
+struct IpResponse {
+    ip: IpAddr,
+}
+
+/// Looks up our address via a provider that returns `{"ip": "..."}`.
+pub struct HttpJsonSource {
+    v4_url: String,
+    v6_url: String,
+}
+
+impl Default for HttpJsonSource {
+    fn default() -> Self {
+        Self {
+            v4_url: DEFAULT_JSON_URL_V4.to_string(),
+            v6_url: DEFAULT_JSON_URL_V6.to_string(),
+        }
+    }
+}
+
+impl IpSource for HttpJsonSource {
+    fn lookup(&self, family: AddressFamily) -> Result<IpAddr, Box<dyn Error>> {
+        let url = match family {
+            AddressFamily::V4 => &self.v4_url,
+            AddressFamily::V6 => &self.v6_url,
+        };
+
+        let body: IpResponse = reqwest::blocking::get(url)?.json()?;
+        Ok(body.ip)
+    }
+}
+
+/// Looks up our address via a provider that returns the plain-text address.
+pub struct HttpTextSource {
+    v4_url: String,
+    v6_url: String,
+}
+
+impl Default for HttpTextSource {
+    fn default() -> Self {
+        Self {
+            v4_url: DEFAULT_TEXT_URL_V4.to_string(),
+            v6_url: DEFAULT_TEXT_URL_V6.to_string(),
+        }
+    }
+}
+
+impl IpSource for HttpTextSource {
+    fn lookup(&self, family: AddressFamily) -> Result<IpAddr, Box<dyn Error>> {
+        let url = match family {
+            AddressFamily::V4 => &self.v4_url,
+            AddressFamily::V6 => &self.v6_url,
+        };
+
+        let body = reqwest::blocking::get(url)?.text()?;
+        Ok(body.trim().parse()?)
+    }
+}
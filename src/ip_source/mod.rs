@@ -0,0 +1,137 @@
+use std::{env, error::Error, net::IpAddr};
+
+use log::{debug, warn};
+
+pub mod http;
+#[cfg(target_os = "linux")]
+pub mod netlink;
+pub mod stun;
+
+const CLOUDFLARE_IP_SOURCES: &str = "CLOUDFLARE_IP_SOURCES";
+#[cfg(target_os = "linux")]
+const CLOUDFLARE_INTERFACE: &str = "CLOUDFLARE_INTERFACE";
+
+/// Which address family a lookup is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressFamily {
+    V4,
+    V6,
+}
+
+/// A way of discovering this host's own public IP address.
+pub trait IpSource {
+    fn lookup(&self, family: AddressFamily) -> Result<IpAddr, Box<dyn Error>>;
+}
+
+/// Builds the ordered list of sources to try, from the comma-separated
+/// `CLOUDFLARE_IP_SOURCES` identifiers (e.g. "stun,https-json"), falling
+/// back to STUN alone when unset to preserve existing behaviour.
+///
+/// When `CLOUDFLARE_INTERFACE` is set (Linux only), the interface's own
+/// address is read directly via netlink instead, skipping the network
+/// round-trip entirely.
+pub fn configured_sources() -> Vec<Box<dyn IpSource>> {
+    #[cfg(target_os = "linux")]
+    if let Ok(interface) = env::var(CLOUDFLARE_INTERFACE) {
+        return vec![Box::new(netlink::NetlinkSource::new(interface)) as Box<dyn IpSource>];
+    }
+
+    let raw = env::var(CLOUDFLARE_IP_SOURCES).unwrap_or_else(|_| "stun".to_string());
+
+    raw.split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .filter_map(|id| match id {
+            "stun" => Some(Box::new(stun::StunSource) as Box<dyn IpSource>),
+            "https-json" => Some(Box::new(http::HttpJsonSource::default()) as Box<dyn IpSource>),
+            "https-text" => Some(Box::new(http::HttpTextSource::default()) as Box<dyn IpSource>),
+            other => {
+                warn!("Unknown IP source '{}', ignoring", other);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Tries each source in order for `family`, returning the first success. A
+/// source that returns an address of the wrong family (a misbehaving or
+/// misconfigured source) is treated the same as a failure, so the fallback
+/// chain keeps going rather than silently handing back a useless answer.
+pub fn lookup_address(sources: &[Box<dyn IpSource>], family: AddressFamily) -> Option<IpAddr> {
+    for source in sources {
+        match source.lookup(family) {
+            Ok(ip) if matches_family(ip, family) => return Some(ip),
+            Ok(ip) => debug!("IP source returned {} for {:?}, ignoring", ip, family),
+            Err(e) => debug!("IP source failed for {:?}: {}", family, e),
+        }
+    }
+    None
+}
+
+fn matches_family(ip: IpAddr, family: AddressFamily) -> bool {
+    matches!(
+        (ip, family),
+        (IpAddr::V4(_), AddressFamily::V4) | (IpAddr::V6(_), AddressFamily::V6)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    struct FailingSource;
+    impl IpSource for FailingSource {
+        fn lookup(&self, _family: AddressFamily) -> Result<IpAddr, Box<dyn Error>> {
+            Err("simulated failure".into())
+        }
+    }
+
+    struct SucceedingSource(IpAddr);
+    impl IpSource for SucceedingSource {
+        fn lookup(&self, _family: AddressFamily) -> Result<IpAddr, Box<dyn Error>> {
+            Ok(self.0)
+        }
+    }
+
+    #[test]
+    fn lookup_address_returns_first_success() {
+        let expected = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 1));
+        let sources: Vec<Box<dyn IpSource>> = vec![
+            Box::new(FailingSource),
+            Box::new(SucceedingSource(expected)),
+        ];
+
+        assert_eq!(lookup_address(&sources, AddressFamily::V4), Some(expected));
+    }
+
+    #[test]
+    fn lookup_address_returns_none_when_all_sources_fail() {
+        let sources: Vec<Box<dyn IpSource>> = vec![Box::new(FailingSource), Box::new(FailingSource)];
+
+        assert_eq!(lookup_address(&sources, AddressFamily::V4), None);
+    }
+
+    #[test]
+    fn lookup_address_skips_source_with_wrong_family() {
+        let wrong_family = IpAddr::V6(Ipv6Addr::new(0x2001, 0x0db8, 0, 0, 0, 0, 0, 1));
+        let expected = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 1));
+        let sources: Vec<Box<dyn IpSource>> = vec![
+            Box::new(SucceedingSource(wrong_family)),
+            Box::new(SucceedingSource(expected)),
+        ];
+
+        assert_eq!(lookup_address(&sources, AddressFamily::V4), Some(expected));
+    }
+
+    #[test]
+    #[serial(change_flare_env)]
+    fn configured_sources_ignores_unknown_identifiers() {
+        env::set_var(CLOUDFLARE_IP_SOURCES, "bogus,stun");
+        let sources = configured_sources();
+        env::remove_var(CLOUDFLARE_IP_SOURCES);
+
+        assert_eq!(sources.len(), 1);
+    }
+}
@@ -0,0 +1,127 @@
+use std::{error::Error, net::IpAddr};
+
+use futures::stream::TryStreamExt;
+use netlink_packet_route::address::AddressAttribute;
+use rtnetlink::new_connection;
+
+use super::{AddressFamily, IpSource};
+
+/// Reads a routable address directly off a local interface via netlink,
+/// instead of asking an external service to reflect our address back to us.
+/// Useful on hosts (VPS, IPv6 deployments) where the interface already
+/// carries a public address and a NAT-reflected lookup would be unnecessary
+/// or simply wrong.
+pub struct NetlinkSource {
+    interface: String,
+}
+
+impl NetlinkSource {
+    pub fn new(interface: String) -> Self {
+        Self { interface }
+    }
+}
+
+impl IpSource for NetlinkSource {
+    fn lookup(&self, family: AddressFamily) -> Result<IpAddr, Box<dyn Error>> {
+        let interface = self.interface.clone();
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        rt.block_on(query_interface_address(interface, family))
+    }
+}
+
+async fn query_interface_address(
+    interface: String,
+    family: AddressFamily,
+) -> Result<IpAddr, Box<dyn Error>> {
+    let (connection, handle, _) = new_connection()?;
+    tokio::spawn(connection);
+
+    let link = handle
+        .link()
+        .get()
+        .match_name(interface.clone())
+        .execute()
+        .try_next()
+        .await?
+        .ok_or_else(|| format!("No such interface: {}", interface))?;
+
+    let mut addresses = handle
+        .address()
+        .get()
+        .set_link_index_filter(link.header.index)
+        .execute();
+
+    while let Some(msg) = addresses.try_next().await? {
+        for attr in &msg.attributes {
+            if let AddressAttribute::Address(addr) = attr {
+                let matches_family = match family {
+                    AddressFamily::V4 => addr.is_ipv4(),
+                    AddressFamily::V6 => addr.is_ipv6(),
+                };
+                if matches_family && is_global(addr) {
+                    return Ok(*addr);
+                }
+            }
+        }
+    }
+
+    Err(format!("No global-scope address found on {}", interface).into())
+}
+
+/// Whether `addr` is a globally-routable address rather than a loopback,
+/// link-local, or private one — the kind of address we actually want to
+/// publish in a DNS record.
+fn is_global(addr: &IpAddr) -> bool {
+    match addr {
+        IpAddr::V4(v4) => !v4.is_private() && !v4.is_loopback() && !v4.is_link_local(),
+        IpAddr::V6(v6) => {
+            // Exclude loopback, link-local (fe80::/10), and unique-local
+            // (fc00::/7, e.g. Docker's/Tailscale's fd00::/8) — none of these
+            // are reachable from the public internet.
+            let first_segment = v6.segments()[0];
+            !v6.is_loopback() && (first_segment & 0xffc0) != 0xfe80 && (first_segment & 0xfe00) != 0xfc00
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv6Addr;
+
+    #[test]
+    fn is_global_accepts_public_addresses() {
+        assert!(is_global(&IpAddr::V4(std::net::Ipv4Addr::new(
+            203, 0, 113, 1
+        ))));
+        assert!(is_global(&IpAddr::V6(Ipv6Addr::new(
+            0x2001, 0x0db8, 0, 0, 0, 0, 0, 1
+        ))));
+    }
+
+    #[test]
+    fn is_global_rejects_private_v4() {
+        assert!(!is_global(&IpAddr::V4(std::net::Ipv4Addr::new(
+            192, 168, 1, 1
+        ))));
+        assert!(!is_global(&IpAddr::V4(std::net::Ipv4Addr::new(127, 0, 0, 1))));
+    }
+
+    #[test]
+    fn is_global_rejects_link_local_and_unique_local_v6() {
+        // fe80::/10 link-local
+        assert!(!is_global(&IpAddr::V6(Ipv6Addr::new(
+            0xfe80, 0, 0, 0, 0, 0, 0, 1
+        ))));
+        // fc00::/7 unique-local
+        assert!(!is_global(&IpAddr::V6(Ipv6Addr::new(
+            0xfd00, 0, 0, 0, 0, 0, 0, 1
+        ))));
+        assert!(!is_global(&IpAddr::V6(Ipv6Addr::new(
+            0xfc00, 0, 0, 0, 0, 0, 0, 1
+        ))));
+        assert!(!is_global(&IpAddr::V6(Ipv6Addr::LOCALHOST)));
+    }
+}
@@ -0,0 +1,32 @@
+use std::{
+    error::Error,
+    net::{IpAddr, SocketAddr, ToSocketAddrs, UdpSocket},
+};
+
+use stunclient::StunClient;
+
+use super::{AddressFamily, IpSource};
+
+/// Discovers our externally-visible address via the Cloudflare STUN server,
+/// as the (sole) original implementation did.
+pub struct StunSource;
+
+impl IpSource for StunSource {
+    fn lookup(&self, family: AddressFamily) -> Result<IpAddr, Box<dyn Error>> {
+        let (local_addr, is_v6) = match family {
+            AddressFamily::V4 => ("0.0.0.0:0", false),
+            AddressFamily::V6 => ("[::]:0", true),
+        };
+
+        let local_addr: SocketAddr = local_addr.parse()?;
+        let udp = UdpSocket::bind(local_addr)?;
+        let stun_server = "stun.cloudflare.com:3478"
+            .to_socket_addrs()?
+            .find(|x| if is_v6 { x.is_ipv6() } else { x.is_ipv4() })
+            .ok_or("No matching address found for STUN server")?;
+
+        let c = StunClient::new(stun_server);
+        let addr = c.query_external_address(&udp)?;
+        Ok(addr.ip())
+    }
+}
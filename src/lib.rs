@@ -0,0 +1,4 @@
+pub mod cache;
+pub mod cloudflare;
+pub mod core;
+pub mod ip_source;
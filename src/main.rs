@@ -2,7 +2,31 @@ use std::thread;
 
 use change_flare::{cloudflare::CloudFlareApi, core::Updater};
 
+use log::LevelFilter;
+
+/// Sets up leveled logging: native systemd journal records when stdout is
+/// connected to the journal (systemd sets `JOURNAL_STREAM` in that case),
+/// otherwise a standard stderr logger honoring `RUST_LOG`.
+fn init_logging() {
+    if std::env::var("JOURNAL_STREAM").is_ok() {
+        let level = std::env::var("RUST_LOG")
+            .ok()
+            .and_then(|val| val.parse::<LevelFilter>().ok())
+            .unwrap_or(LevelFilter::Info);
+
+        systemd_journal_logger::JournalLog::new()
+            .expect("failed to connect to systemd journal")
+            .install()
+            .expect("failed to install journal logger");
+        log::set_max_level(level);
+    } else {
+        env_logger::init();
+    }
+}
+
 fn main() {
+    init_logging();
+
     // Create updater with record
     let mut updater = Updater::<CloudFlareApi>::default();
 